@@ -5,7 +5,7 @@
 
 use crossterm::{
     cursor::{Hide, MoveTo},
-    event::EnableMouseCapture,
+    event::{Event, EnableMouseCapture, KeyCode, MouseButton, MouseEventKind, poll, read},
     execute, queue,
     style::{Color, Print, SetBackgroundColor, SetForegroundColor},
     terminal::{
@@ -14,18 +14,33 @@ use crossterm::{
 };
 use serde::Deserialize;
 use std::{
+    cell::RefCell,
+    collections::HashMap,
     io::{Write, stdout},
     os::raw::c_int,
     slice,
     sync::Mutex,
+    time::Duration,
 };
 use taffy::prelude::*;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
 static MAX_BUFFER_SIZE: usize = 2_000_000;
 static LAST_BUFFER: Mutex<Option<Box<[u64; MAX_BUFFER_SIZE]>>> = Mutex::new(None);
 static CURRENT_BUFFER: Mutex<Option<Box<[u64; MAX_BUFFER_SIZE]>>> = Mutex::new(None);
 static TERMINAL_SIZE: Mutex<(u16, u16)> = Mutex::new((0, 0));
 static FRAMES: Mutex<Option<Vec<f32>>> = Mutex::new(None);
+static FRAME_IDS: Mutex<Option<Vec<i64>>> = Mutex::new(None);
+static LAST_EVENT: Mutex<Option<Vec<u64>>> = Mutex::new(None);
+
+thread_local! {
+    // `TaffyTree`'s `Style` stores `Dimension`/`CompactLength` as a tagged
+    // pointer, which makes `TaffyTree` (and `RetainedTree`, which embeds one)
+    // `!Send`. Bun calls into this FFI from a single thread, so a
+    // thread-local `RefCell` gives the same "survives across calls" behavior
+    // as the other `static Mutex<_>`s above without requiring `Send`.
+    static RETAINED_TREE: RefCell<Option<RetainedTree>> = const { RefCell::new(None) };
+}
 
 #[unsafe(no_mangle)]
 pub extern "C" fn init_buffer() -> c_int {
@@ -88,13 +103,34 @@ pub extern "C" fn flush() -> c_int {
                 let (w, h) = *term_size;
                 let used_cells = (w as usize) * (h as usize);
 
-                for (cell_idx, (new, old)) in buf[0..used_cells * 3]
-                    .chunks_exact(3)
-                    .zip(last_buf[0..used_cells * 3].chunks_exact(3))
-                    .enumerate()
-                {
-                    if new != old {
-                        let codepoint_code = char::from_u32(new[0] as u32).unwrap();
+                // A codepoint of 0 marks the continuation cell of a wide
+                // (display-width 2) glyph to its left; it carries no glyph
+                // of its own and is never emitted directly.
+                let mut cell_idx = 0usize;
+                while cell_idx < used_cells {
+                    let base = cell_idx * 3;
+                    let new = &buf[base..base + 3];
+                    let old = &last_buf[base..base + 3];
+
+                    let codepoint = new[0] as u32;
+                    if codepoint == 0 {
+                        cell_idx += 1;
+                        continue;
+                    }
+
+                    let codepoint_code = char::from_u32(codepoint).unwrap();
+                    let width = codepoint_code.width().unwrap_or(1).max(1);
+
+                    // A wide glyph invalidates its neighbor: either cell
+                    // changing means the whole glyph needs to be repainted.
+                    let mut changed = new != old;
+                    if width == 2 && cell_idx + 1 < used_cells {
+                        let next_base = (cell_idx + 1) * 3;
+                        changed = changed
+                            || buf[next_base..next_base + 3] != last_buf[next_base..next_base + 3];
+                    }
+
+                    if changed {
                         let fg = new[1];
                         let fg_code = Color::Rgb {
                             r: ((fg >> 16) & 0xFF) as u8,
@@ -120,6 +156,10 @@ pub extern "C" fn flush() -> c_int {
                         )
                         .unwrap();
                     }
+
+                    // Wide glyphs occupy the next buffer cell too; skip
+                    // emitting it separately rather than painting it again.
+                    cell_idx += width;
                 }
                 stdout.flush().unwrap();
                 if let Some(ref buf) = *cb {
@@ -173,8 +213,64 @@ pub extern "C" fn update_terminal_size() -> c_int {
     1
 }
 
+#[derive(Deserialize, Debug, Clone)]
+#[serde(untagged)]
+enum GridTrackSize {
+    Fixed(f32),
+    Keyword(String),
+}
+
+fn to_track_sizing_function(track: &GridTrackSize) -> GridTemplateComponent<TrackSizingFunction> {
+    let sizing_function = match track {
+        GridTrackSize::Fixed(size) => length(*size),
+        GridTrackSize::Keyword(keyword) => {
+            if let Some(fraction) = keyword.strip_suffix("fr") {
+                fraction.parse::<f32>().map(fr).unwrap_or_else(|_| auto())
+            } else {
+                match keyword.as_str() {
+                    "auto" => auto(),
+                    "min-content" => min_content(),
+                    "max-content" => max_content(),
+                    _ => auto(),
+                }
+            }
+        }
+    };
+    GridTemplateComponent::Single(sizing_function)
+}
+
+#[derive(Deserialize, Debug, Clone, Copy)]
+struct GridLinePlacement {
+    start: i16,
+    end: i16,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(untagged)]
+enum SizeValue {
+    Fixed(f32),
+    Keyword(String),
+}
+
+fn to_dimension(value: &SizeValue) -> Dimension {
+    match value {
+        SizeValue::Fixed(size) => length(*size),
+        SizeValue::Keyword(keyword) => match keyword.as_str() {
+            "auto" => auto(),
+            "full" => percent(1.0),
+            percentage if percentage.ends_with('%') => percentage
+                .trim_end_matches('%')
+                .parse::<f32>()
+                .map(|p| percent(p / 100.0))
+                .unwrap_or_else(|_| auto()),
+            _ => auto(),
+        },
+    }
+}
+
 #[derive(Deserialize, Debug)]
 struct Node {
+    id: i64,
     #[serde(rename = "type")]
     node_type: String,
     gap: f32,
@@ -184,6 +280,24 @@ struct Node {
     padding_y: f32,
     border: f32,
     text: String,
+    #[serde(rename = "gridTemplateColumns", default)]
+    grid_template_columns: Option<Vec<GridTrackSize>>,
+    #[serde(rename = "gridTemplateRows", default)]
+    grid_template_rows: Option<Vec<GridTrackSize>>,
+    #[serde(rename = "gridColumn", default)]
+    grid_column: Option<GridLinePlacement>,
+    #[serde(rename = "gridRow", default)]
+    grid_row: Option<GridLinePlacement>,
+    #[serde(default)]
+    width: Option<SizeValue>,
+    #[serde(default)]
+    height: Option<SizeValue>,
+    #[serde(rename = "flexGrow", default)]
+    flex_grow: Option<f32>,
+    #[serde(rename = "flexShrink", default)]
+    flex_shrink: Option<f32>,
+    #[serde(rename = "flexBasis", default)]
+    flex_basis: Option<SizeValue>,
     children: Vec<Node>,
 }
 
@@ -195,7 +309,7 @@ struct Tree {
 }
 
 fn get_styles(node: &Node) -> Style {
-    Style {
+    let mut style = Style {
         gap: Size {
             width: length(node.gap),
             height: zero(),
@@ -213,33 +327,315 @@ fn get_styles(node: &Node) -> Style {
             bottom: length(node.border),
         },
         ..Default::default()
+    };
+
+    if let Some(columns) = &node.grid_template_columns {
+        style.grid_template_columns = columns.iter().map(to_track_sizing_function).collect();
+    }
+    if let Some(rows) = &node.grid_template_rows {
+        style.grid_template_rows = rows.iter().map(to_track_sizing_function).collect();
+    }
+    if let Some(grid_column) = node.grid_column {
+        style.grid_column = Line {
+            start: line(grid_column.start),
+            end: line(grid_column.end),
+        };
     }
+    if let Some(grid_row) = node.grid_row {
+        style.grid_row = Line {
+            start: line(grid_row.start),
+            end: line(grid_row.end),
+        };
+    }
+
+    if let Some(width) = &node.width {
+        style.size.width = to_dimension(width);
+    }
+    if let Some(height) = &node.height {
+        style.size.height = to_dimension(height);
+    }
+    if let Some(flex_grow) = node.flex_grow {
+        style.flex_grow = flex_grow;
+    }
+    if let Some(flex_shrink) = node.flex_shrink {
+        style.flex_shrink = flex_shrink;
+    }
+    if let Some(flex_basis) = &node.flex_basis {
+        style.flex_basis = to_dimension(flex_basis);
+    }
+
+    style
 }
 
-fn build_taffy_tree(taffy: &mut TaffyTree<()>, taffy_root: &NodeId, tree_node: &Node) {
-    for child in &tree_node.children {
-        let mut child_styles = get_styles(child);
+// Reused from both a fresh style build and `update_node_style` on an
+// existing node, so each arm must set every field the other arms touch
+// (not just the one it cares about) or switching mode on a live node
+// leaves stale fields from its previous mode in place.
+fn apply_container_mode(style: &mut Style, node_type: &str) {
+    match node_type {
+        "column" => {
+            style.display = Display::Flex;
+            style.flex_direction = FlexDirection::Column;
+        }
+        "row" => {
+            style.display = Display::Flex;
+            style.flex_direction = FlexDirection::Row;
+        }
+        "grid" => {
+            style.display = Display::Grid;
+            style.flex_direction = FlexDirection::Row;
+        }
+        _ => {
+            style.display = Display::Flex;
+            style.flex_direction = FlexDirection::Row;
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Default)]
+struct NodeStyleUpdate {
+    #[serde(rename = "type", default)]
+    node_type: Option<String>,
+    #[serde(default)]
+    gap: Option<f32>,
+    #[serde(rename = "paddingX", default)]
+    padding_x: Option<f32>,
+    #[serde(rename = "paddingY", default)]
+    padding_y: Option<f32>,
+    #[serde(default)]
+    border: Option<f32>,
+    #[serde(rename = "gridTemplateColumns", default)]
+    grid_template_columns: Option<Vec<GridTrackSize>>,
+    #[serde(rename = "gridTemplateRows", default)]
+    grid_template_rows: Option<Vec<GridTrackSize>>,
+    #[serde(rename = "gridColumn", default)]
+    grid_column: Option<GridLinePlacement>,
+    #[serde(rename = "gridRow", default)]
+    grid_row: Option<GridLinePlacement>,
+    #[serde(default)]
+    width: Option<SizeValue>,
+    #[serde(default)]
+    height: Option<SizeValue>,
+    #[serde(rename = "flexGrow", default)]
+    flex_grow: Option<f32>,
+    #[serde(rename = "flexShrink", default)]
+    flex_shrink: Option<f32>,
+    #[serde(rename = "flexBasis", default)]
+    flex_basis: Option<SizeValue>,
+}
 
-        let flex_direction: Option<FlexDirection> = match child.node_type.as_str() {
-            "column" => Some(FlexDirection::Column),
-            "row" => Some(FlexDirection::Row),
-            _ => None,
+// Applies only the fields present in `update`, leaving the rest of `style`
+// (and anything the caller doesn't know about) untouched.
+fn apply_style_update(style: &mut Style, update: &NodeStyleUpdate) {
+    if let Some(node_type) = &update.node_type {
+        apply_container_mode(style, node_type.as_str());
+    }
+    if let Some(gap) = update.gap {
+        style.gap.width = length(gap);
+    }
+    if let Some(padding_x) = update.padding_x {
+        style.padding.left = length(padding_x);
+        style.padding.right = length(padding_x);
+    }
+    if let Some(padding_y) = update.padding_y {
+        style.padding.top = length(padding_y);
+        style.padding.bottom = length(padding_y);
+    }
+    if let Some(border) = update.border {
+        style.border = Rect {
+            left: length(border),
+            right: length(border),
+            top: length(border),
+            bottom: length(border),
         };
-        if let Some(fd) = flex_direction {
-            child_styles.flex_direction = fd;
+    }
+    if let Some(columns) = &update.grid_template_columns {
+        style.grid_template_columns = columns.iter().map(to_track_sizing_function).collect();
+    }
+    if let Some(rows) = &update.grid_template_rows {
+        style.grid_template_rows = rows.iter().map(to_track_sizing_function).collect();
+    }
+    if let Some(grid_column) = update.grid_column {
+        style.grid_column = Line {
+            start: line(grid_column.start),
+            end: line(grid_column.end),
         };
+    }
+    if let Some(grid_row) = update.grid_row {
+        style.grid_row = Line {
+            start: line(grid_row.start),
+            end: line(grid_row.end),
+        };
+    }
+    if let Some(width) = &update.width {
+        style.size.width = to_dimension(width);
+    }
+    if let Some(height) = &update.height {
+        style.size.height = to_dimension(height);
+    }
+    if let Some(flex_grow) = update.flex_grow {
+        style.flex_grow = flex_grow;
+    }
+    if let Some(flex_shrink) = update.flex_shrink {
+        style.flex_shrink = flex_shrink;
+    }
+    if let Some(flex_basis) = &update.flex_basis {
+        style.flex_basis = to_dimension(flex_basis);
+    }
+}
+
+enum NodeContext {
+    Text(String),
+    Button(String),
+    Container,
+}
+
+fn node_context_for(node: &Node) -> NodeContext {
+    match node.node_type.as_str() {
+        "column" | "row" | "grid" => NodeContext::Container,
+        "text" => NodeContext::Text(node.text.clone()),
+        "button" => NodeContext::Button(node.text.clone()),
+        _ => NodeContext::Container,
+    }
+}
+
+// Keeps a `TaffyTree` alive across FFI calls so incremental edits reuse
+// taffy's per-node measurement cache instead of rebuilding from scratch.
+struct RetainedTree {
+    taffy: TaffyTree<NodeContext>,
+    root: NodeId,
+    width: f32,
+    height: f32,
+    node_to_id: HashMap<NodeId, i64>,
+    id_to_node: HashMap<i64, NodeId>,
+}
+
+// Mirrored by hand in playground/src/main.rs (no shared crate between the
+// cdylib and the binary yet) — keep both in sync if this logic changes.
+fn longest_word_width(text: &str) -> f32 {
+    text.split_whitespace()
+        .map(|word| word.width() as f32)
+        .fold(0.0, f32::max)
+}
+
+fn unwrapped_width(text: &str) -> f32 {
+    text.width() as f32
+}
+
+fn resolve_target_width(text: &str, available_width: AvailableSpace) -> f32 {
+    match available_width {
+        AvailableSpace::Definite(width) => width,
+        AvailableSpace::MinContent => longest_word_width(text),
+        AvailableSpace::MaxContent => unwrapped_width(text),
+    }
+}
+
+// Greedily packs whitespace-separated words into lines no wider than
+// `target_width`, breaking a word across lines only when it alone overflows.
+fn wrap_text(text: &str, target_width: f32) -> Size<f32> {
+    if target_width <= 0.0 {
+        return Size::ZERO;
+    }
+
+    let mut longest_line = 0.0f32;
+    let mut line_count = 0u32;
+    let mut current_line_width = 0.0f32;
+    let mut line_has_word = false;
+
+    for word in text.split_whitespace() {
+        let word_width = word.width() as f32;
+
+        if word_width > target_width {
+            if line_has_word {
+                longest_line = longest_line.max(current_line_width);
+                line_count += 1;
+            }
+            let mut remaining = word_width;
+            while remaining > target_width {
+                longest_line = longest_line.max(target_width);
+                line_count += 1;
+                remaining -= target_width;
+            }
+            current_line_width = remaining;
+            line_has_word = true;
+            continue;
+        }
+
+        let gap = if line_has_word { 1.0 } else { 0.0 };
+        if line_has_word && current_line_width + gap + word_width > target_width {
+            longest_line = longest_line.max(current_line_width);
+            line_count += 1;
+            current_line_width = word_width;
+        } else {
+            current_line_width += gap + word_width;
+            line_has_word = true;
+        }
+    }
+
+    if line_has_word {
+        longest_line = longest_line.max(current_line_width);
+        line_count += 1;
+    }
+
+    Size {
+        width: longest_line,
+        height: line_count.max(1) as f32,
+    }
+}
+
+fn measure_function(
+    known_dimensions: Size<Option<f32>>,
+    available_space: Size<AvailableSpace>,
+    _node_id: NodeId,
+    node_context: Option<&mut NodeContext>,
+    _style: &Style,
+) -> Size<f32> {
+    if let Size {
+        width: Some(width),
+        height: Some(height),
+    } = known_dimensions
+    {
+        return Size { width, height };
+    }
+
+    match node_context {
+        Some(NodeContext::Text(text)) | Some(NodeContext::Button(text)) => {
+            let target_width = known_dimensions
+                .width
+                .unwrap_or_else(|| resolve_target_width(text, available_space.width));
+
+            wrap_text(text, target_width)
+        }
+        _ => Size::ZERO,
+    }
+}
+
+fn build_taffy_tree(
+    taffy: &mut TaffyTree<NodeContext>,
+    taffy_root: &NodeId,
+    tree_node: &Node,
+    node_ids: &mut HashMap<NodeId, i64>,
+) {
+    for child in &tree_node.children {
+        let mut child_styles = get_styles(child);
+        apply_container_mode(&mut child_styles, child.node_type.as_str());
 
-        let taffy_child = taffy.new_leaf(child_styles).unwrap();
+        let taffy_child = taffy
+            .new_leaf_with_context(child_styles, node_context_for(child))
+            .unwrap();
         taffy.add_child(*taffy_root, taffy_child).unwrap();
+        node_ids.insert(taffy_child, child.id);
 
-        build_taffy_tree(taffy, &taffy_child, child);
+        build_taffy_tree(taffy, &taffy_child, child, node_ids);
     }
 }
 
 fn build_frames_array(
-    taffy: &mut TaffyTree<()>,
+    taffy: &mut TaffyTree<NodeContext>,
     node: NodeId,
+    node_ids: &HashMap<NodeId, i64>,
     out: &mut Vec<f32>,
+    out_ids: &mut Vec<i64>,
     offset_x: f32,
     offset_y: f32,
 ) -> taffy::TaffyResult<()> {
@@ -254,10 +650,11 @@ fn build_frames_array(
         layout.size.width,
         layout.size.height,
     ]);
+    out_ids.push(node_ids.get(&node).copied().unwrap_or(-1));
 
     let children = taffy.children(node).unwrap();
     for child in children {
-        build_frames_array(taffy, child, out, absolute_x, absolute_y)?;
+        build_frames_array(taffy, child, node_ids, out, out_ids, absolute_x, absolute_y)?;
     }
 
     Ok(())
@@ -265,41 +662,48 @@ fn build_frames_array(
 
 #[unsafe(no_mangle)]
 pub extern "C" fn calculate_layout(p: *const u8, l: u32) -> c_int {
+    // This is a one-shot rebuild; drop any retained tree so a later
+    // incremental call doesn't resume mutating a stale one.
+    RETAINED_TREE.with(|cell| *cell.borrow_mut() = None);
+
     let json_bytes = unsafe { slice::from_raw_parts(p, l as usize) };
-    let tree = serde_json::from_slice::<Tree>(json_bytes).unwrap();
+    let tree = match serde_json::from_slice::<Tree>(json_bytes) {
+        Ok(tree) => tree,
+        Err(_) => return 0,
+    };
 
-    let mut taffy: TaffyTree<()> = TaffyTree::new();
+    let mut taffy: TaffyTree<NodeContext> = TaffyTree::new();
 
     let node = &tree.node;
 
-    let flex_direction: Option<FlexDirection> = match node.node_type.as_str() {
-        "column" => Some(FlexDirection::Column),
-        "row" => Some(FlexDirection::Row),
-        _ => None,
-    };
-
     let mut root_styles = get_styles(node);
-    if let Some(fd) = flex_direction {
-        root_styles.flex_direction = fd;
-    };
-    let root = taffy.new_leaf(root_styles).unwrap();
+    apply_container_mode(&mut root_styles, node.node_type.as_str());
+    let root = taffy
+        .new_leaf_with_context(root_styles, node_context_for(node))
+        .unwrap();
+
+    let mut node_ids: HashMap<NodeId, i64> = HashMap::new();
+    node_ids.insert(root, node.id);
 
-    build_taffy_tree(&mut taffy, &root, &tree.node);
+    build_taffy_tree(&mut taffy, &root, &tree.node, &mut node_ids);
 
-    let _ = taffy.compute_layout(
+    let _ = taffy.compute_layout_with_measure(
         root,
         Size {
             width: length(tree.width),
             height: length(tree.height),
         },
+        measure_function,
     );
     // taffy.print_tree(root);
 
     let mut frames: Vec<f32> = Vec::new();
+    let mut ids: Vec<i64> = Vec::new();
 
-    build_frames_array(&mut taffy, root, &mut frames, 0.0, 0.0).unwrap();
+    build_frames_array(&mut taffy, root, &node_ids, &mut frames, &mut ids, 0.0, 0.0).unwrap();
 
     *FRAMES.lock().unwrap() = Some(frames);
+    *FRAME_IDS.lock().unwrap() = Some(ids);
     1
 }
 
@@ -321,6 +725,207 @@ pub extern "C" fn get_frames_len() -> u64 {
     }
 }
 
+#[unsafe(no_mangle)]
+pub extern "C" fn get_ids_ptr() -> *const i64 {
+    let ids = FRAME_IDS.lock().unwrap();
+    match *ids {
+        Some(ref vec) => vec.as_ptr(),
+        None => std::ptr::null(),
+    }
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn get_ids_len() -> u64 {
+    let ids = FRAME_IDS.lock().unwrap();
+    match *ids {
+        Some(ref vec) => vec.len() as u64,
+        None => 0,
+    }
+}
+
+// The frames/ids lists are in paint order (parents before children, siblings
+// in order), so the topmost node at a point is the *last* matching rect.
+#[unsafe(no_mangle)]
+pub extern "C" fn hit_test(x: u16, y: u16) -> i64 {
+    let frames = FRAMES.lock().unwrap();
+    let ids = FRAME_IDS.lock().unwrap();
+
+    let (Some(frames), Some(ids)) = (frames.as_ref(), ids.as_ref()) else {
+        return -1;
+    };
+
+    let point_x = x as f32;
+    let point_y = y as f32;
+
+    for (rect, id) in frames.chunks_exact(4).zip(ids.iter()).rev() {
+        let (rect_x, rect_y, width, height) = (rect[0], rect[1], rect[2], rect[3]);
+
+        if point_x >= rect_x
+            && point_x < rect_x + width
+            && point_y >= rect_y
+            && point_y < rect_y + height
+        {
+            return *id;
+        }
+    }
+
+    -1
+}
+
+fn recompute_and_cache_frames(tree: &mut RetainedTree) {
+    let _ = tree.taffy.compute_layout_with_measure(
+        tree.root,
+        Size {
+            width: length(tree.width),
+            height: length(tree.height),
+        },
+        measure_function,
+    );
+
+    let mut frames: Vec<f32> = Vec::new();
+    let mut ids: Vec<i64> = Vec::new();
+
+    build_frames_array(
+        &mut tree.taffy,
+        tree.root,
+        &tree.node_to_id,
+        &mut frames,
+        &mut ids,
+        0.0,
+        0.0,
+    )
+    .unwrap();
+
+    *FRAMES.lock().unwrap() = Some(frames);
+    *FRAME_IDS.lock().unwrap() = Some(ids);
+}
+
+// Builds the retained tree once so later edits only touch the node(s) that
+// changed, letting taffy's cache skip recomputing untouched subtrees.
+#[unsafe(no_mangle)]
+pub extern "C" fn build_retained_tree(p: *const u8, l: u32) -> c_int {
+    let json_bytes = unsafe { slice::from_raw_parts(p, l as usize) };
+    let tree = match serde_json::from_slice::<Tree>(json_bytes) {
+        Ok(tree) => tree,
+        Err(_) => return 0,
+    };
+
+    let mut taffy: TaffyTree<NodeContext> = TaffyTree::new();
+
+    let node = &tree.node;
+
+    let mut root_styles = get_styles(node);
+    apply_container_mode(&mut root_styles, node.node_type.as_str());
+    let root = taffy
+        .new_leaf_with_context(root_styles, node_context_for(node))
+        .unwrap();
+
+    let mut node_to_id: HashMap<NodeId, i64> = HashMap::new();
+    node_to_id.insert(root, node.id);
+
+    build_taffy_tree(&mut taffy, &root, &tree.node, &mut node_to_id);
+
+    let id_to_node: HashMap<i64, NodeId> =
+        node_to_id.iter().map(|(node, id)| (*id, *node)).collect();
+
+    let mut retained = RetainedTree {
+        taffy,
+        root,
+        width: tree.width,
+        height: tree.height,
+        node_to_id,
+        id_to_node,
+    };
+
+    recompute_and_cache_frames(&mut retained);
+
+    RETAINED_TREE.with(|cell| *cell.borrow_mut() = Some(retained));
+    1
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn update_node_style(id: i64, p: *const u8, l: u32) -> c_int {
+    let json_bytes = unsafe { slice::from_raw_parts(p, l as usize) };
+    let update = match serde_json::from_slice::<NodeStyleUpdate>(json_bytes) {
+        Ok(update) => update,
+        Err(_) => return 0,
+    };
+
+    RETAINED_TREE.with(|cell| {
+        let mut retained_tree = cell.borrow_mut();
+        let Some(ref mut tree) = *retained_tree else {
+            return 0;
+        };
+        let Some(&node_id) = tree.id_to_node.get(&id) else {
+            return 0;
+        };
+
+        let mut style = match tree.taffy.style(node_id) {
+            Ok(style) => style.clone(),
+            Err(_) => return 0,
+        };
+        apply_style_update(&mut style, &update);
+
+        if tree.taffy.set_style(node_id, style).is_err() {
+            return 0;
+        }
+
+        1
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn update_node_text(id: i64, p: *const u8, l: u32) -> c_int {
+    let json_bytes = unsafe { slice::from_raw_parts(p, l as usize) };
+    let text = match std::str::from_utf8(json_bytes) {
+        Ok(text) => text.to_string(),
+        Err(_) => return 0,
+    };
+
+    RETAINED_TREE.with(|cell| {
+        let mut retained_tree = cell.borrow_mut();
+        let Some(ref mut tree) = *retained_tree else {
+            return 0;
+        };
+        let Some(&node_id) = tree.id_to_node.get(&id) else {
+            return 0;
+        };
+
+        let new_context = match tree.taffy.get_node_context(node_id) {
+            Some(NodeContext::Button(_)) => NodeContext::Button(text),
+            Some(NodeContext::Text(_)) => NodeContext::Text(text),
+            // Container nodes have no text of their own; rewriting one into a
+            // Text/Button context would change how it's measured if it's ever
+            // childless. Only text/button node ids are valid targets here.
+            Some(NodeContext::Container) | None => return 0,
+        };
+
+        // set_node_context already marks the node dirty internally.
+        if tree
+            .taffy
+            .set_node_context(node_id, Some(new_context))
+            .is_err()
+        {
+            return 0;
+        }
+
+        1
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn recompute_retained_layout() -> c_int {
+    RETAINED_TREE.with(|cell| {
+        let mut retained_tree = cell.borrow_mut();
+        let Some(ref mut tree) = *retained_tree else {
+            return 0;
+        };
+
+        recompute_and_cache_frames(tree);
+        1
+    })
+}
+
 #[unsafe(no_mangle)]
 pub extern "C" fn debug_buffer(idx: u64) -> u64 {
     let cb = CURRENT_BUFFER.lock().unwrap();
@@ -335,14 +940,112 @@ pub extern "C" fn debug_buffer(idx: u64) -> u64 {
     }
 }
 
-// fn print_events() -> io::Result<bool> {
-//     loop {
-//         if poll(Duration::from_millis(100))? {
-//             // It's guaranteed that `read` won't block, because `poll` returned
-//             // `Ok(true)`.
-//             println!("{:?}", read()?);
-//         } else {
-//             // Timeout expired, no `Event` is available
-//         }
-//     }
-// }
+// event-kind tags for the encoded LAST_EVENT record.
+const EVENT_KIND_KEY: u64 = 0;
+const EVENT_KIND_MOUSE: u64 = 1;
+const EVENT_KIND_RESIZE: u64 = 2;
+const EVENT_KIND_OTHER: u64 = 255;
+
+fn encode_key_code(code: KeyCode) -> (u64, u64) {
+    match code {
+        KeyCode::Char(c) => (0, c as u64),
+        KeyCode::Enter => (1, 0),
+        KeyCode::Esc => (2, 0),
+        KeyCode::Backspace => (3, 0),
+        KeyCode::Tab => (4, 0),
+        KeyCode::BackTab => (5, 0),
+        KeyCode::Up => (6, 0),
+        KeyCode::Down => (7, 0),
+        KeyCode::Left => (8, 0),
+        KeyCode::Right => (9, 0),
+        KeyCode::Home => (10, 0),
+        KeyCode::End => (11, 0),
+        KeyCode::PageUp => (12, 0),
+        KeyCode::PageDown => (13, 0),
+        KeyCode::Delete => (14, 0),
+        KeyCode::Insert => (15, 0),
+        KeyCode::F(n) => (16, n as u64),
+        _ => (EVENT_KIND_OTHER, 0),
+    }
+}
+
+fn encode_mouse_button(button: MouseButton) -> u64 {
+    match button {
+        MouseButton::Left => 1,
+        MouseButton::Right => 2,
+        MouseButton::Middle => 3,
+    }
+}
+
+// button_tag: 1x = down, 2x = up, 3x = drag (x = button from
+// encode_mouse_button), 40 = moved, 41..44 = scroll up/down/left/right.
+fn encode_mouse_kind(kind: MouseEventKind) -> u64 {
+    match kind {
+        MouseEventKind::Down(button) => 10 + encode_mouse_button(button),
+        MouseEventKind::Up(button) => 20 + encode_mouse_button(button),
+        MouseEventKind::Drag(button) => 30 + encode_mouse_button(button),
+        MouseEventKind::Moved => 40,
+        MouseEventKind::ScrollUp => 41,
+        MouseEventKind::ScrollDown => 42,
+        MouseEventKind::ScrollLeft => 43,
+        MouseEventKind::ScrollRight => 44,
+    }
+}
+
+fn encode_event(event: Event) -> Vec<u64> {
+    match event {
+        Event::Key(key_event) => {
+            let (code_tag, code_value) = encode_key_code(key_event.code);
+            vec![
+                EVENT_KIND_KEY,
+                code_tag,
+                code_value,
+                key_event.modifiers.bits() as u64,
+            ]
+        }
+        Event::Mouse(mouse_event) => vec![
+            EVENT_KIND_MOUSE,
+            encode_mouse_kind(mouse_event.kind),
+            mouse_event.column as u64,
+            mouse_event.row as u64,
+            mouse_event.modifiers.bits() as u64,
+        ],
+        Event::Resize(width, height) => vec![EVENT_KIND_RESIZE, width as u64, height as u64],
+        _ => vec![EVENT_KIND_OTHER],
+    }
+}
+
+// Returns 1 when an event was read into LAST_EVENT, 0 on timeout with no
+// event available, and -1 if crossterm's poll/read failed.
+#[unsafe(no_mangle)]
+pub extern "C" fn poll_event(timeout_ms: u64) -> c_int {
+    match poll(Duration::from_millis(timeout_ms)) {
+        Ok(true) => match read() {
+            Ok(event) => {
+                *LAST_EVENT.lock().unwrap() = Some(encode_event(event));
+                1
+            }
+            Err(_) => -1,
+        },
+        Ok(false) => 0,
+        Err(_) => -1,
+    }
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn get_event_ptr() -> *const u64 {
+    let event = LAST_EVENT.lock().unwrap();
+    match *event {
+        Some(ref vec) => vec.as_ptr(),
+        None => std::ptr::null(),
+    }
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn get_event_len() -> u64 {
+    let event = LAST_EVENT.lock().unwrap();
+    match *event {
+        Some(ref vec) => vec.len() as u64,
+        None => 0,
+    }
+}
@@ -2,6 +2,7 @@ use std::fs;
 
 use serde::Deserialize;
 use taffy::prelude::*;
+use unicode_width::UnicodeWidthStr;
 
 #[derive(Deserialize, Debug)]
 struct Node {
@@ -112,12 +113,85 @@ fn build_frames_array(
     }
 }
 
+// Mirrored by hand in letui-ffi/src/lib.rs (no shared crate between the
+// cdylib and this binary yet) — keep both in sync if this logic changes.
+fn longest_word_width(text: &str) -> f32 {
+    text.split_whitespace()
+        .map(|word| word.width() as f32)
+        .fold(0.0, f32::max)
+}
+
+fn unwrapped_width(text: &str) -> f32 {
+    text.width() as f32
+}
+
+fn resolve_target_width(text: &str, available_width: AvailableSpace) -> f32 {
+    match available_width {
+        AvailableSpace::Definite(width) => width,
+        AvailableSpace::MinContent => longest_word_width(text),
+        AvailableSpace::MaxContent => unwrapped_width(text),
+    }
+}
+
+// Greedily packs whitespace-separated words into lines no wider than
+// `target_width`, breaking a word across lines only when it alone overflows.
+fn wrap_text(text: &str, target_width: f32) -> Size<f32> {
+    if target_width <= 0.0 {
+        return Size::ZERO;
+    }
+
+    let mut longest_line = 0.0f32;
+    let mut line_count = 0u32;
+    let mut current_line_width = 0.0f32;
+    let mut line_has_word = false;
+
+    for word in text.split_whitespace() {
+        let word_width = word.width() as f32;
+
+        if word_width > target_width {
+            if line_has_word {
+                longest_line = longest_line.max(current_line_width);
+                line_count += 1;
+            }
+            let mut remaining = word_width;
+            while remaining > target_width {
+                longest_line = longest_line.max(target_width);
+                line_count += 1;
+                remaining -= target_width;
+            }
+            current_line_width = remaining;
+            line_has_word = true;
+            continue;
+        }
+
+        let gap = if line_has_word { 1.0 } else { 0.0 };
+        if line_has_word && current_line_width + gap + word_width > target_width {
+            longest_line = longest_line.max(current_line_width);
+            line_count += 1;
+            current_line_width = word_width;
+        } else {
+            current_line_width += gap + word_width;
+            line_has_word = true;
+        }
+    }
+
+    if line_has_word {
+        longest_line = longest_line.max(current_line_width);
+        line_count += 1;
+    }
+
+    Size {
+        width: longest_line,
+        height: line_count.max(1) as f32,
+    }
+}
+
 fn measure_function(
     known_dimensions: Size<Option<f32>>,
-    _available_space: Size<AvailableSpace>,
+    available_space: Size<AvailableSpace>,
     _node_id: NodeId,
     node_context: Option<&mut NodeContext>,
-    style: &Style,
+    _style: &Style,
 ) -> Size<f32> {
     if let Size {
         width: Some(width),
@@ -129,12 +203,11 @@ fn measure_function(
 
     match node_context {
         Some(NodeContext::Text(text)) | Some(NodeContext::Button(text)) => {
-            let text_width = text.chars().count() as f32;
+            let target_width = known_dimensions
+                .width
+                .unwrap_or_else(|| resolve_target_width(text, available_space.width));
 
-            Size {
-                width: text_width,
-                height: 1.0,
-            }
+            wrap_text(text, target_width)
         }
         _ => Size::ZERO,
     }